@@ -0,0 +1,321 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::parser::{ApexClass, ApexParser};
+
+const CACHE_FILE_NAME: &str = ".apex-ts-cache";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    len: u64,
+    mtime: u64,
+    hash: String,
+    class: Option<ApexClass>,
+    /// `true` ならこのパスは前回 `parse_file` がエラーを返したためにキャッシュされた `None`。
+    /// `@tsexport` が単に無かった場合の `None` と区別し、ファイルが直らない限り黙って
+    /// 失敗し続けていることに気付けるようにする。
+    #[serde(default)]
+    parse_failed: bool,
+}
+
+/// `.apex-ts-cache` サイドカーファイルに永続化する、ファイルパスごとの解析結果キャッシュ。
+/// 長さとハッシュが一致する限り `ApexParser::parse_file` を呼び直さずに済ませる。
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Cache {
+    entries: HashMap<String, CacheEntry>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl Cache {
+    fn load(cache_dir: &Path) -> Self {
+        fs::read_to_string(cache_file_path(cache_dir))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, cache_dir: &Path) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        fs::create_dir_all(cache_dir).with_context(|| {
+            format!("Failed to create cache directory: {}", cache_dir.display())
+        })?;
+        let path = cache_file_path(cache_dir);
+        let content = serde_json::to_string(self).context("Failed to serialize cache")?;
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write cache file: {}", path.display()))
+    }
+
+    /// 長さとハッシュが前回と一致すればキャッシュ済みの結果を返し、そうでなければ `parse` を
+    /// 呼んで結果（とパース失敗したかどうか）を記録する。`parse` がエラーを返しても
+    /// 呼び出し元へは伝播させず、`None` として記録した上で警告を出す
+    /// （`ApexParser::parse_files`/`watch::run` と同様、1 ファイルの失敗で全体を止めない）。
+    fn get_or_parse(
+        &mut self,
+        path: &str,
+        len: u64,
+        mtime: u64,
+        content: &[u8],
+        parse: impl FnOnce(&[u8]) -> Result<Option<ApexClass>>,
+    ) -> Option<ApexClass> {
+        let hash = hash_bytes(content);
+
+        if let Some(entry) = self.entries.get(path) {
+            if entry.len == len && entry.hash == hash {
+                return entry.class.clone();
+            }
+        }
+
+        let (class, parse_failed) = match parse(content) {
+            Ok(class) => (class, false),
+            Err(e) => {
+                warn!("Skipping {} (failed to parse): {:#}", path, e);
+                (None, true)
+            }
+        };
+        self.entries.insert(
+            path.to_string(),
+            CacheEntry {
+                len,
+                mtime,
+                hash,
+                class: class.clone(),
+                parse_failed,
+            },
+        );
+        self.dirty = true;
+        class
+    }
+
+    fn prune(&mut self, existing_paths: &[String]) {
+        let existing: HashSet<&str> = existing_paths.iter().map(String::as_str).collect();
+        let before = self.entries.len();
+        self.entries
+            .retain(|path, _| existing.contains(path.as_str()));
+        if self.entries.len() != before {
+            self.dirty = true;
+        }
+    }
+}
+
+fn cache_file_path(cache_dir: &Path) -> PathBuf {
+    cache_dir.join(CACHE_FILE_NAME)
+}
+
+fn hash_bytes(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// `parser.parse_files` と同じ結果を、`cache_dir` のサイドカーキャッシュを使って高速に返す。
+/// 変更のないファイル（長さとハッシュが一致するもの）は再パースせずキャッシュを再利用する。
+pub fn parse_files_cached(
+    parser: &ApexParser,
+    paths: &[String],
+    cache_dir: &Path,
+) -> Result<Vec<ApexClass>> {
+    let mut cache = Cache::load(cache_dir);
+    let mut classes = Vec::new();
+
+    for path in paths {
+        let metadata =
+            fs::metadata(path).with_context(|| format!("Failed to stat file: {}", path))?;
+        let content = fs::read(path).with_context(|| format!("Failed to read file: {}", path))?;
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let class = cache.get_or_parse(path, metadata.len(), mtime, &content, |bytes| {
+            parser.parse_file(&String::from_utf8_lossy(bytes))
+        });
+
+        if let Some(class) = class {
+            classes.push(class);
+        }
+    }
+
+    cache.prune(paths);
+
+    let previously_failed = paths
+        .iter()
+        .filter(|p| {
+            cache
+                .entries
+                .get(p.as_str())
+                .is_some_and(|e| e.parse_failed)
+        })
+        .count();
+    if previously_failed > 0 {
+        warn!(
+            "{} cached file(s) previously failed to parse; rerun with --no-cache to see the error(s) again",
+            previously_failed
+        );
+    }
+
+    cache.save(cache_dir)?;
+
+    Ok(classes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_class(name: &str) -> ApexClass {
+        ApexClass {
+            name: name.to_string(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            inner_classes: Vec::new(),
+            enums: Vec::new(),
+            missing_annotations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn get_or_parse_reuses_cached_result_for_unchanged_content() {
+        let mut cache = Cache::default();
+        let content = b"class Foo {}";
+        let mut calls = 0;
+
+        let class = cache.get_or_parse("Foo.cls", content.len() as u64, 0, content, |_| {
+            calls += 1;
+            Ok(Some(test_class("Foo")))
+        });
+        assert_eq!(class.unwrap().name, "Foo");
+
+        let class = cache.get_or_parse("Foo.cls", content.len() as u64, 0, content, |_| {
+            calls += 1;
+            Ok(Some(test_class("Foo")))
+        });
+        assert_eq!(class.unwrap().name, "Foo");
+        assert_eq!(calls, 1, "unchanged content must not be reparsed");
+    }
+
+    #[test]
+    fn get_or_parse_reparses_when_length_changes() {
+        let mut cache = Cache::default();
+        let mut calls = 0;
+
+        let a = b"class Foo {}";
+        cache.get_or_parse("Foo.cls", a.len() as u64, 0, a, |_| {
+            calls += 1;
+            Ok(Some(test_class("Foo")))
+        });
+
+        let b = b"class FooLonger {}";
+        let class = cache.get_or_parse("Foo.cls", b.len() as u64, 0, b, |_| {
+            calls += 1;
+            Ok(Some(test_class("FooLonger")))
+        });
+
+        assert_eq!(class.unwrap().name, "FooLonger");
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn get_or_parse_reparses_same_length_different_content() {
+        let mut cache = Cache::default();
+        let mut calls = 0;
+
+        let a = b"class Foo12 {}";
+        let b = b"class Foo34 {}";
+        assert_eq!(a.len(), b.len(), "test fixture must keep lengths equal");
+
+        cache.get_or_parse("Foo.cls", a.len() as u64, 0, a, |_| {
+            calls += 1;
+            Ok(Some(test_class("Foo12")))
+        });
+
+        let class = cache.get_or_parse("Foo.cls", b.len() as u64, 0, b, |_| {
+            calls += 1;
+            Ok(Some(test_class("Foo34")))
+        });
+
+        assert_eq!(class.unwrap().name, "Foo34");
+        assert_eq!(
+            calls, 2,
+            "same length but different hash must still reparse"
+        );
+    }
+
+    #[test]
+    fn get_or_parse_caches_parse_failures_without_reparsing() {
+        let mut cache = Cache::default();
+        let content = b"not real apex";
+
+        let class = cache.get_or_parse("Bad.cls", content.len() as u64, 0, content, |_| {
+            anyhow::bail!("boom")
+        });
+        assert!(class.is_none());
+        assert!(cache.entries.get("Bad.cls").unwrap().parse_failed);
+
+        // Unchanged content: must hit the cache rather than calling `parse` again.
+        let class = cache.get_or_parse("Bad.cls", content.len() as u64, 0, content, |_| {
+            panic!("must not reparse unchanged content");
+        });
+        assert!(class.is_none());
+    }
+
+    #[test]
+    fn prune_removes_entries_for_paths_no_longer_present() {
+        let mut cache = Cache::default();
+        cache.entries.insert(
+            "Keep.cls".to_string(),
+            CacheEntry {
+                len: 1,
+                mtime: 0,
+                hash: "h".to_string(),
+                class: None,
+                parse_failed: false,
+            },
+        );
+        cache.entries.insert(
+            "Gone.cls".to_string(),
+            CacheEntry {
+                len: 1,
+                mtime: 0,
+                hash: "h".to_string(),
+                class: None,
+                parse_failed: false,
+            },
+        );
+
+        cache.prune(&["Keep.cls".to_string()]);
+
+        assert!(cache.entries.contains_key("Keep.cls"));
+        assert!(!cache.entries.contains_key("Gone.cls"));
+        assert!(cache.dirty);
+    }
+
+    #[test]
+    fn prune_is_a_no_op_when_nothing_is_removed() {
+        let mut cache = Cache::default();
+        cache.entries.insert(
+            "Keep.cls".to_string(),
+            CacheEntry {
+                len: 1,
+                mtime: 0,
+                hash: "h".to_string(),
+                class: None,
+                parse_failed: false,
+            },
+        );
+
+        cache.prune(&["Keep.cls".to_string()]);
+
+        assert!(!cache.dirty);
+    }
+}
@@ -0,0 +1,249 @@
+//! A small hand-rolled tokenizer for Apex source.
+//!
+//! This classifies source text into identifiers/keywords, literals, single-character
+//! punctuation, and comment spans, while tracking byte offsets and line numbers so the
+//! parser can reason about nesting (braces, angle brackets, parens) instead of relying on
+//! flat regexes.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// Identifier or keyword (Apex keywords aren't reserved at the lexer level).
+    Ident(String),
+    /// String literal, content between the quotes (escapes left as-is).
+    Str(String),
+    /// Character/single-quoted literal, content between the quotes.
+    Char(String),
+    /// Numeric literal, raw text.
+    Number(String),
+    /// Single-character punctuation, e.g. `{`, `}`, `(`, `)`, `<`, `>`, `,`, `;`, `@`.
+    Punct(char),
+    /// `// ...` comment, content after the slashes.
+    LineComment(String),
+    /// `/* ... */` or `/** ... */` comment, raw content between the delimiters.
+    BlockComment(String),
+    Eof,
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+}
+
+/// A byte range plus the 1-based line it starts on, carried alongside parsed items so
+/// editor-facing consumers (diagnostics, hover) can map back to source positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+}
+
+impl From<&Token> for Span {
+    fn from(token: &Token) -> Self {
+        Span {
+            start: token.start,
+            end: token.end,
+            line: token.line,
+        }
+    }
+}
+
+impl Token {
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::LineComment(_) | TokenKind::BlockComment(_)
+        )
+    }
+}
+
+struct Lexer<'a> {
+    src: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            bytes: src.as_bytes(),
+            pos: 0,
+            line: 1,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.bytes.get(self.pos + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<u8> {
+        let b = self.peek()?;
+        self.pos += 1;
+        if b == b'\n' {
+            self.line += 1;
+        }
+        Some(b)
+    }
+
+    fn next_token(&mut self) -> Token {
+        // 空白をスキップ
+        while matches!(self.peek(), Some(b) if b.is_ascii_whitespace()) {
+            self.bump();
+        }
+
+        let start = self.pos;
+        let line = self.line;
+
+        let Some(b) = self.peek() else {
+            return Token {
+                kind: TokenKind::Eof,
+                start,
+                end: start,
+                line,
+            };
+        };
+
+        let kind = match b {
+            b'/' if self.peek_at(1) == Some(b'/') => self.lex_line_comment(),
+            b'/' if self.peek_at(1) == Some(b'*') => self.lex_block_comment(),
+            b'"' => self.lex_string(),
+            b'\'' => self.lex_char(),
+            b'0'..=b'9' => self.lex_number(),
+            b if is_ident_start(b) => self.lex_ident(),
+            _ => {
+                self.bump();
+                TokenKind::Punct(b as char)
+            }
+        };
+
+        Token {
+            kind,
+            start,
+            end: self.pos,
+            line,
+        }
+    }
+
+    fn lex_line_comment(&mut self) -> TokenKind {
+        let content_start = self.pos + 2;
+        self.bump();
+        self.bump();
+        while !matches!(self.peek(), None | Some(b'\n')) {
+            self.bump();
+        }
+        TokenKind::LineComment(self.src[content_start..self.pos].to_string())
+    }
+
+    fn lex_block_comment(&mut self) -> TokenKind {
+        self.bump();
+        self.bump();
+        let content_start = self.pos;
+        let content_end;
+        loop {
+            match (self.peek(), self.peek_at(1)) {
+                (Some(b'*'), Some(b'/')) => {
+                    content_end = self.pos;
+                    self.bump();
+                    self.bump();
+                    break;
+                }
+                (None, _) => {
+                    content_end = self.pos;
+                    break;
+                }
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+        TokenKind::BlockComment(self.src[content_start..content_end].to_string())
+    }
+
+    fn lex_string(&mut self) -> TokenKind {
+        let content_start = self.pos + 1;
+        self.bump();
+        while let Some(b) = self.peek() {
+            match b {
+                b'\\' => {
+                    self.bump();
+                    self.bump();
+                }
+                b'"' => break,
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+        let content_end = self.pos;
+        self.bump();
+        TokenKind::Str(self.src[content_start..content_end].to_string())
+    }
+
+    fn lex_char(&mut self) -> TokenKind {
+        let content_start = self.pos + 1;
+        self.bump();
+        while let Some(b) = self.peek() {
+            match b {
+                b'\\' => {
+                    self.bump();
+                    self.bump();
+                }
+                b'\'' => break,
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+        let content_end = self.pos;
+        self.bump();
+        TokenKind::Char(self.src[content_start..content_end].to_string())
+    }
+
+    fn lex_number(&mut self) -> TokenKind {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b) if b.is_ascii_digit() || b == b'.') {
+            self.bump();
+        }
+        TokenKind::Number(self.src[start..self.pos].to_string())
+    }
+
+    fn lex_ident(&mut self) -> TokenKind {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b) if is_ident_continue(b)) {
+            self.bump();
+        }
+        TokenKind::Ident(self.src[start..self.pos].to_string())
+    }
+}
+
+fn is_ident_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_'
+}
+
+fn is_ident_continue(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// Tokenizes Apex source into a flat token stream terminated by `TokenKind::Eof`.
+pub fn tokenize(src: &str) -> Vec<Token> {
+    let mut lexer = Lexer::new(src);
+    let mut tokens = Vec::new();
+    loop {
+        let token = lexer.next_token();
+        let is_eof = matches!(token.kind, TokenKind::Eof);
+        tokens.push(token);
+        if is_eof {
+            break;
+        }
+    }
+    tokens
+}
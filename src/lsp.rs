@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use tokio::sync::Mutex;
+use tower_lsp::jsonrpc::Result as LspResult;
+use tower_lsp::lsp_types::*;
+use tower_lsp::{async_trait, Client, LanguageServer, LspService, Server};
+
+use crate::lexer::Span;
+use crate::parser::{ApexClass, ApexParser};
+
+/// `apex-ts lsp` の本体。開いている `.cls` ファイルについて、`@AuraEnabled` が
+/// 欠けている公開メンバーへの診断と、フィールド/メソッドの生成予定の TypeScript 型の
+/// hover を提供する。
+pub fn run() -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let stdin = tokio::io::stdin();
+        let stdout = tokio::io::stdout();
+        let (service, socket) = LspService::new(Backend::new);
+        Server::new(stdin, stdout, socket).serve(service).await;
+    });
+    Ok(())
+}
+
+struct Backend {
+    client: Client,
+    documents: Mutex<HashMap<Url, String>>,
+}
+
+impl Backend {
+    fn new(client: Client) -> Self {
+        Self {
+            client,
+            documents: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn on_change(&self, uri: Url, text: String) {
+        let diagnostics = diagnostics_for(&text);
+        self.documents.lock().await.insert(uri.clone(), text);
+        self.client
+            .publish_diagnostics(uri, diagnostics, None)
+            .await;
+    }
+}
+
+#[async_trait]
+impl LanguageServer for Backend {
+    async fn initialize(&self, _: InitializeParams) -> LspResult<InitializeResult> {
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                    TextDocumentSyncKind::FULL,
+                )),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                ..ServerCapabilities::default()
+            },
+            ..InitializeResult::default()
+        })
+    }
+
+    async fn initialized(&self, _: InitializedParams) {
+        self.client
+            .log_message(MessageType::INFO, "apex-ts language server initialized")
+            .await;
+        tracing::warn!(
+            "hover types are computed by a separate Apex->TypeScript mapping (to_ts_type in \
+             lsp.rs), not by generator::TypeScriptGenerator; hover text can differ from the \
+             generated types.d.ts."
+        );
+    }
+
+    async fn shutdown(&self) -> LspResult<()> {
+        Ok(())
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        self.on_change(params.text_document.uri, params.text_document.text)
+            .await;
+    }
+
+    async fn did_change(&self, mut params: DidChangeTextDocumentParams) {
+        // フルシンクなので最後の変更内容がドキュメント全体
+        if let Some(change) = params.content_changes.pop() {
+            self.on_change(params.text_document.uri, change.text).await;
+        }
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        self.documents
+            .lock()
+            .await
+            .remove(&params.text_document.uri);
+    }
+
+    async fn hover(&self, params: HoverParams) -> LspResult<Option<Hover>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+        let documents = self.documents.lock().await;
+        Ok(documents
+            .get(&uri)
+            .and_then(|text| hover_for(text, position)))
+    }
+}
+
+fn diagnostics_for(text: &str) -> Vec<Diagnostic> {
+    let parser = ApexParser::new().expect("ApexParser::new is infallible");
+    let Ok(Some(class)) = parser.parse_file(text) else {
+        return Vec::new();
+    };
+
+    let mut diagnostics = Vec::new();
+    collect_missing_annotation_diagnostics(&class, text, &mut diagnostics);
+    diagnostics
+}
+
+fn collect_missing_annotation_diagnostics(
+    class: &ApexClass,
+    text: &str,
+    out: &mut Vec<Diagnostic>,
+) {
+    for missing in &class.missing_annotations {
+        out.push(Diagnostic {
+            range: span_to_range(text, missing.span),
+            severity: Some(DiagnosticSeverity::WARNING),
+            source: Some("apex-ts".to_string()),
+            message: format!(
+                "'{}' is public but missing @AuraEnabled, so it won't be included in the generated TypeScript types",
+                missing.member_name
+            ),
+            ..Diagnostic::default()
+        });
+    }
+    for inner in &class.inner_classes {
+        collect_missing_annotation_diagnostics(inner, text, out);
+    }
+}
+
+fn hover_for(text: &str, position: Position) -> Option<Hover> {
+    let parser = ApexParser::new().ok()?;
+    let class = parser.parse_file(text).ok().flatten()?;
+    let offset = position_to_offset(text, position)?;
+    find_member_hover(&class, offset)
+}
+
+fn find_member_hover(class: &ApexClass, offset: usize) -> Option<Hover> {
+    for field in &class.fields {
+        if contains(field.span, offset) {
+            return Some(make_hover(format!(
+                "{}: {}",
+                field.name,
+                to_ts_type(&field.field_type)
+            )));
+        }
+    }
+    for method in &class.methods {
+        if contains(method.span, offset) {
+            let params = method
+                .parameters
+                .iter()
+                .map(|p| format!("{}: {}", p.name, to_ts_type(&p.param_type)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Some(make_hover(format!(
+                "{}({}): {}",
+                method.name,
+                params,
+                to_ts_type(&method.return_type)
+            )));
+        }
+    }
+    class
+        .inner_classes
+        .iter()
+        .find_map(|inner| find_member_hover(inner, offset))
+}
+
+fn contains(span: Span, offset: usize) -> bool {
+    offset >= span.start && offset < span.end
+}
+
+fn make_hover(type_text: String) -> Hover {
+    Hover {
+        contents: HoverContents::Scalar(MarkedString::from_language_code(
+            "typescript".to_string(),
+            type_text,
+        )),
+        range: None,
+    }
+}
+
+/// hover 用の最小限の Apex → TypeScript 型マッピング。`generator::TypeScriptGenerator` の
+/// マッピングとは共有していないため、hover に出る型が生成された型定義と一致するとは限らない。
+fn to_ts_type(apex_type: &str) -> String {
+    let trimmed = apex_type.trim();
+    match trimmed.to_ascii_lowercase().as_str() {
+        "string" | "id" => "string".to_string(),
+        "integer" | "long" | "decimal" | "double" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "void" => "void".to_string(),
+        _ => {
+            if let Some(inner) =
+                strip_generic(trimmed, "List").or_else(|| strip_generic(trimmed, "Set"))
+            {
+                return format!("{}[]", to_ts_type(inner));
+            }
+            if let Some(inner) = strip_generic(trimmed, "Map") {
+                if let Some((_, value)) = split_top_level_comma(inner) {
+                    return format!("Record<string, {}>", to_ts_type(value.trim()));
+                }
+            }
+            trimmed.to_string()
+        }
+    }
+}
+
+fn strip_generic<'a>(apex_type: &'a str, name: &str) -> Option<&'a str> {
+    let prefix_len = name.len() + 1; // "Name<"
+    if apex_type.len() > prefix_len
+        && apex_type[..name.len()].eq_ignore_ascii_case(name)
+        && apex_type.as_bytes()[name.len()] == b'<'
+        && apex_type.ends_with('>')
+    {
+        Some(&apex_type[prefix_len..apex_type.len() - 1])
+    } else {
+        None
+    }
+}
+
+fn split_top_level_comma(s: &str) -> Option<(&str, &str)> {
+    let mut depth = 0i32;
+    for (i, ch) in s.char_indices() {
+        match ch {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => return Some((&s[..i], &s[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn span_to_range(text: &str, span: Span) -> Range {
+    Range::new(
+        offset_to_position(text, span.start),
+        offset_to_position(text, span.end),
+    )
+}
+
+fn offset_to_position(text: &str, offset: usize) -> Position {
+    let mut line = 0u32;
+    let mut last_newline = 0usize;
+    for (i, b) in text.as_bytes().iter().enumerate() {
+        if i >= offset {
+            break;
+        }
+        if *b == b'\n' {
+            line += 1;
+            last_newline = i + 1;
+        }
+    }
+    let character = text[last_newline..offset].encode_utf16().count() as u32;
+    Position::new(line, character)
+}
+
+fn position_to_offset(text: &str, position: Position) -> Option<usize> {
+    let mut offset = 0usize;
+    for (i, line) in text.split('\n').enumerate() {
+        if i as u32 == position.line {
+            let utf16_target = position.character as usize;
+            let mut utf16_count = 0usize;
+            for (byte_idx, ch) in line.char_indices() {
+                if utf16_count >= utf16_target {
+                    return Some(offset + byte_idx);
+                }
+                utf16_count += ch.len_utf16();
+            }
+            return Some(offset + line.len());
+        }
+        offset += line.len() + 1;
+    }
+    None
+}
@@ -1,22 +1,36 @@
+mod cache;
+mod lexer;
+mod lsp;
+mod mode;
 mod parser;
 mod generator;
+mod watch;
 
 use anyhow::{Context, Result};
-use clap::Parser;
-use std::fs;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 use walkdir::WalkDir;
 
 use crate::generator::TypeScriptGenerator;
-use crate::parser::ApexParser;
+use crate::mode::Mode;
+use crate::parser::{warn_about_unemitted_nested_types, ApexParser};
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run a language server exposing @AuraEnabled diagnostics and hover for .cls files
+    Lsp,
+}
 
 #[derive(Parser, Debug)]
 #[command(name = "apex-ts")]
 #[command(about = "Generate TypeScript type definitions from Apex classes with @tsexport annotation", long_about = None)]
 struct Cli {
-    /// Input directory containing Apex class files (.cls)
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Input directory containing Apex class files (.cls). Required unless running `lsp`.
     #[arg(short, long, value_name = "DIR")]
-    input: PathBuf,
+    input: Option<PathBuf>,
 
     /// Output TypeScript file path
     #[arg(short, long, value_name = "FILE", default_value = "types.d.ts")]
@@ -25,28 +39,58 @@ struct Cli {
     /// Verbose output
     #[arg(short, long)]
     verbose: bool,
+
+    /// Watch the input directory and incrementally regenerate on file changes
+    #[arg(long)]
+    watch: bool,
+
+    /// Disable the content-hash cache and reparse every file
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Directory for the content-hash cache sidecar file
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    cache_dir: PathBuf,
+
+    /// Verify that --output is up to date instead of writing it (for CI)
+    #[arg(long)]
+    check: bool,
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    // tracing の初期化
+    // tracing の初期化（lsp モードは stdio で LSP プロトコルを喋るので、ログは必ず stderr に出す）
     if cli.verbose {
         tracing_subscriber::fmt()
             .with_max_level(tracing::Level::DEBUG)
+            .with_writer(std::io::stderr)
             .init();
     } else {
         tracing_subscriber::fmt()
             .with_max_level(tracing::Level::WARN)
+            .with_writer(std::io::stderr)
             .init();
     }
 
+    if let Some(Command::Lsp) = cli.command {
+        return lsp::run();
+    }
+
+    let input = cli
+        .input
+        .context("--input is required (unless running `apex-ts lsp`)")?;
+
+    if cli.watch {
+        return watch::run(&input, &cli.output, cli.verbose);
+    }
+
     if cli.verbose {
-        println!("Scanning directory: {}", cli.input.display());
+        println!("Scanning directory: {}", input.display());
     }
 
     // .cls ファイルを収集
-    let apex_files: Vec<String> = WalkDir::new(&cli.input)
+    let apex_files: Vec<String> = WalkDir::new(&input)
         .into_iter()
         .filter_map(|e| e.ok())
         .filter(|e| e.path().extension().is_some_and(|ext| ext == "cls"))
@@ -57,15 +101,21 @@ fn main() -> Result<()> {
         println!("Found {} Apex class files", apex_files.len());
     }
 
+    // Apex クラスを解析（--no-cache が指定されていなければコンテンツハッシュキャッシュを使う）。
+    // apex_files が空でもここで cache::parse_files_cached を呼ぶことで、削除/移動された
+    // ファイルのキャッシュエントリが確実に prune される。
+    let parser = ApexParser::new()?;
+    let classes = if cli.no_cache {
+        parser.parse_files(&apex_files)?
+    } else {
+        cache::parse_files_cached(&parser, &apex_files, &cli.cache_dir)?
+    };
+
     if apex_files.is_empty() {
-        println!("No Apex class files (.cls) found in {}", cli.input.display());
+        println!("No Apex class files (.cls) found in {}", input.display());
         return Ok(());
     }
 
-    // Apex クラスを解析
-    let parser = ApexParser::new()?;
-    let classes = parser.parse_files(&apex_files)?;
-
     if cli.verbose {
         println!("Found {} classes with @tsexport annotation", classes.len());
     }
@@ -75,23 +125,14 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    warn_about_unemitted_nested_types(&classes);
+
     // TypeScript 型定義を生成
     let generator = TypeScriptGenerator::new();
     let typescript_code = generator.generate(&classes);
 
-    // ファイルに書き込み
-    if let Some(parent) = cli.output.parent() {
-        fs::create_dir_all(parent)
-            .with_context(|| format!("Failed to create output directory: {}", parent.display()))?;
-    }
-
-    fs::write(&cli.output, typescript_code)
-        .with_context(|| format!("Failed to write output file: {}", cli.output.display()))?;
-
-    println!(
-        "✓ Successfully generated TypeScript definitions: {}",
-        cli.output.display()
-    );
+    let mode = if cli.check { Mode::Verify } else { Mode::Write };
+    mode::apply(mode, &cli.output, &typescript_code)?;
     println!("  {} interface(s) generated", classes.len());
 
     Ok(())
@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+/// 生成した TypeScript をどう扱うか。rust-analyzer の xtask における
+/// overwrite/verify モードと同様、書き込みと検証が同じコード経路を通ることで両者が食い違わないようにする。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// `--output` にそのまま書き込む
+    Write,
+    /// 既存の `--output` と比較し、一致しなければ失敗させる（CI 向け）
+    Verify,
+}
+
+/// 生成結果を `mode` に従って書き込むか検証する。
+pub fn apply(mode: Mode, output: &Path, typescript_code: &str) -> Result<()> {
+    match mode {
+        Mode::Write => {
+            if let Some(parent) = output.parent() {
+                fs::create_dir_all(parent).with_context(|| {
+                    format!("Failed to create output directory: {}", parent.display())
+                })?;
+            }
+            fs::write(output, typescript_code)
+                .with_context(|| format!("Failed to write output file: {}", output.display()))?;
+            println!(
+                "✓ Successfully generated TypeScript definitions: {}",
+                output.display()
+            );
+            Ok(())
+        }
+        Mode::Verify => {
+            let existing = fs::read_to_string(output).unwrap_or_default();
+            if existing == typescript_code {
+                println!("✓ {} is up to date", output.display());
+                return Ok(());
+            }
+
+            bail!(
+                "{} is out of date with the current Apex classes.\nRun apex-ts without --check to regenerate it.\n{}",
+                output.display(),
+                diff_summary(&existing, typescript_code)
+            );
+        }
+    }
+}
+
+/// 変更された行（インターフェース/フィールド単位でおおむね一行に対応する）だけを抜き出した、簡易 diff。
+fn diff_summary(old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let old_set: HashSet<&str> = old_lines.iter().copied().collect();
+    let new_set: HashSet<&str> = new_lines.iter().copied().collect();
+
+    let mut summary = String::new();
+    for line in &old_lines {
+        if !new_set.contains(line) {
+            summary.push_str("- ");
+            summary.push_str(line);
+            summary.push('\n');
+        }
+    }
+    for line in &new_lines {
+        if !old_set.contains(line) {
+            summary.push_str("+ ");
+            summary.push_str(line);
+            summary.push('\n');
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// テストごとに衝突しない一時ファイルパスを作る（このツリーに `tempfile` クレートはない）。
+    fn temp_output_path(name: &str) -> std::path::PathBuf {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let unique = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!(
+            "apex_ts_mode_test_{}_{}_{}",
+            std::process::id(),
+            unique,
+            name
+        ))
+    }
+
+    #[test]
+    fn write_creates_parent_dir_and_writes_file() {
+        let dir = temp_output_path("write_dir");
+        let output = dir.join("nested").join("types.d.ts");
+
+        apply(Mode::Write, &output, "export interface Foo {}\n").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "export interface Foo {}\n"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn verify_succeeds_when_output_matches() {
+        let output = temp_output_path("verify_match.d.ts");
+        fs::write(&output, "export interface Foo {}\n").unwrap();
+
+        let result = apply(Mode::Verify, &output, "export interface Foo {}\n");
+        assert!(result.is_ok());
+
+        fs::remove_file(&output).unwrap();
+    }
+
+    #[test]
+    fn verify_fails_with_diff_summary_when_output_is_stale() {
+        let output = temp_output_path("verify_mismatch.d.ts");
+        fs::write(&output, "export interface Foo {}\n").unwrap();
+
+        let err = apply(Mode::Verify, &output, "export interface Bar {}\n").unwrap_err();
+        let message = format!("{:#}", err);
+
+        assert!(message.contains("out of date"));
+        assert!(message.contains("- export interface Foo {}"));
+        assert!(message.contains("+ export interface Bar {}"));
+
+        fs::remove_file(&output).unwrap();
+    }
+
+    #[test]
+    fn verify_treats_missing_output_as_empty() {
+        let output = temp_output_path("verify_missing.d.ts");
+
+        let err = apply(Mode::Verify, &output, "export interface Foo {}\n").unwrap_err();
+        let message = format!("{:#}", err);
+
+        assert!(message.contains("+ export interface Foo {}"));
+    }
+
+    #[test]
+    fn diff_summary_reports_only_changed_lines() {
+        let old = "export interface Foo {\n  id: string;\n}\n";
+        let new = "export interface Foo {\n  id: string;\n  name: string;\n}\n";
+
+        let summary = diff_summary(old, new);
+
+        assert_eq!(summary, "+   name: string;\n");
+    }
+}
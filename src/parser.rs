@@ -1,191 +1,899 @@
-use anyhow::{Context, Result};
-use regex::Regex;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
 use std::fs;
 use tracing::{debug, warn};
 
-#[derive(Debug, Clone)]
+use crate::lexer::{self, Span, Token, TokenKind};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApexClass {
     pub name: String,
     pub fields: Vec<ApexField>,
     pub methods: Vec<ApexMethod>,
+    /// クラス本体に直接ネストした `public class` 宣言。
+    /// NOTE: パーサーのモデルに保持されるだけで、`generator::TypeScriptGenerator` の出力には
+    /// 含まれない（修飾名での参照も生成されない）。
+    pub inner_classes: Vec<ApexClass>,
+    /// クラス本体に直接ネストした `enum` 宣言。inner_classes 同様、TypeScript の出力には含まれない。
+    pub enums: Vec<ApexEnum>,
+    /// `public` だが `@AuraEnabled` を欠いているためフィールド/メソッドとして採用されなかったメンバー
+    pub missing_annotations: Vec<MissingAnnotation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApexEnum {
+    pub name: String,
+    pub values: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApexField {
     pub name: String,
     pub field_type: String,
     pub is_optional: bool,
+    pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApexMethod {
     pub name: String,
     pub return_type: String,
     pub parameters: Vec<ApexParameter>,
     pub is_static: bool,
+    pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApexParameter {
     pub name: String,
     pub param_type: String,
 }
 
-pub struct ApexParser {
-    class_regex: Regex,
-    field_with_line_regex: Regex,
-    method_regex: Regex,
-    annotation_regex: Regex,
-    aura_enabled_regex: Regex,
+/// `public` な宣言ながら `@AuraEnabled` が付いていなかったメンバーの記録。
+/// LSP が「エクスポートされるはずなのにされていない」診断を出すために使う。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingAnnotation {
+    pub member_name: String,
+    pub span: Span,
+}
+
+/// メンバー宣言の前に現れる `final`/`virtual` などの修飾子（型を持たないもの）。
+/// `static` はここに含めず、`parse_member_after_public` が別途扱う
+const MEMBER_MODIFIERS: &[&str] = &["final", "virtual", "abstract", "override", "transient"];
+/// `public` の後に続きうる、クラス宣言自体の修飾子
+const CLASS_MODIFIERS: &[&str] = &[
+    "static",
+    "virtual",
+    "abstract",
+    "final",
+    "with",
+    "without",
+    "inherited",
+    "sharing",
+];
+
+/// `parse_class_body` が一つのクラス本体走査中に積み上げていく結果
+#[derive(Default)]
+struct ClassBody {
+    fields: Vec<ApexField>,
+    methods: Vec<ApexMethod>,
+    inner_classes: Vec<ApexClass>,
+    enums: Vec<ApexEnum>,
+    missing_annotations: Vec<MissingAnnotation>,
 }
 
+pub struct ApexParser;
+
 impl ApexParser {
     pub fn new() -> Result<Self> {
-        Ok(Self {
-            // /** ... @tsexport ... */ 複数行ドキュメントコメント内のどこかに@tsexportがあればOK
-            annotation_regex: Regex::new(r"/\*\*[\s\S]*?@tsexport[\s\S]*?\*/")?,
-            // @AuraEnabled または @AuraEnabled(パラメータ) を検出
-            aura_enabled_regex: Regex::new(r"@AuraEnabled(?:\([^)]*\))?")?,
-            // public class ClassName の形式を検出
-            class_regex: Regex::new(r"(?m)^\s*public\s+class\s+(\w+)")?,
-            // フィールド定義を検出（複数行アノテーションとワンライン形式の両方に対応）
-            // キャプチャ: 1=直前の行のアノテーション, 2=同じ行のアノテーション(optional), 3=型, 4=フィールド名
-            field_with_line_regex: Regex::new(
-                r"(?m)((?:^\s*(?:/\*\*[\s\S]*?\*/|@\w+(?:\([^)]*\))?)\s*\n)*)\s*(@\w+(?:\([^)]*\))?\s+)?public\s+(\w+(?:<[\w\s,]+>)?)\s+(\w+)\s*;"
-            )?,
-            // メソッド定義を検出（複数行アノテーションとワンライン形式の両方に対応）
-            method_regex: Regex::new(
-                r"(?m)((?:^\s*(?:/\*\*[\s\S]*?\*/|@\w+(?:\([^)]*\))?)\s*\n)*)\s*(@\w+(?:\([^)]*\))?\s+)?public\s+(static\s+)?(\w+(?:<[\w\s,]+>)?)\s+(\w+)\s*\(([^)]*)\)"
-            )?,
-        })
+        Ok(Self)
     }
 
     pub fn parse_file(&self, content: &str) -> Result<Option<ApexClass>> {
-        // @tsexport アノテーションがあるかチェック
-        if !self.annotation_regex.is_match(content) {
+        let tokens = lexer::tokenize(content);
+
+        // @tsexport アノテーション付きの doc コメントがあるかチェック
+        if !has_tsexport_annotation(&tokens) {
             return Ok(None);
         }
 
-        // クラス名を取得
-        let class_name = self
-            .class_regex
-            .captures(content)
-            .and_then(|cap| cap.get(1))
-            .map(|m| m.as_str().to_string())
-            .context("Failed to find class name")?;
-
-        debug!("Parsing class: {}", class_name);
-
-        // フィールドを解析
-        let mut fields = Vec::new();
-        for cap in self.field_with_line_regex.captures_iter(content) {
-            let prev_line_annotations = cap.get(1).unwrap().as_str();
-            let same_line_annotation = cap.get(2).map(|m| m.as_str()).unwrap_or("");
-            let field_type = cap.get(3).unwrap().as_str().to_string();
-            let field_name = cap.get(4).unwrap().as_str().to_string();
-            
-            // 直前の行または同じ行に @AuraEnabled があるかチェック
-            let has_aura_enabled = self.aura_enabled_regex.is_match(prev_line_annotations) 
-                || self.aura_enabled_regex.is_match(same_line_annotation);
-            
-            if has_aura_enabled {
-                debug!("  Field: {} ({})", field_name, field_type);
-                fields.push(ApexField {
-                    name: field_name,
-                    field_type,
-                    is_optional: false,
-                });
-            } else {
-                warn!(
-                    "  Skipping field '{}' in class '{}' (missing @AuraEnabled)",
-                    field_name, class_name
-                );
-            }
-        }
-
-        // メソッドを解析
-        let mut methods = Vec::new();
-        for cap in self.method_regex.captures_iter(content) {
-            let prev_line_annotations = cap.get(1).unwrap().as_str();
-            let same_line_annotation = cap.get(2).map(|m| m.as_str()).unwrap_or("");
-            let is_static = cap.get(3).is_some();
-            let return_type = cap.get(4).unwrap().as_str().to_string();
-            let method_name = cap.get(5).unwrap().as_str().to_string();
-            let params_str = cap.get(6).unwrap().as_str();
-
-            // 直前の行または同じ行に @AuraEnabled があるかチェック
-            let has_aura_enabled = self.aura_enabled_regex.is_match(prev_line_annotations) 
-                || self.aura_enabled_regex.is_match(same_line_annotation);
-
-            if has_aura_enabled {
-                let parameters = self.parse_parameters(params_str);
-                debug!(
-                    "  Method: {} ({}) -> {}",
-                    method_name,
-                    params_str,
-                    return_type
-                );
-                
-                methods.push(ApexMethod {
-                    name: method_name,
-                    return_type,
-                    parameters,
-                    is_static,
+        let mut cursor = Cursor::new(content, &tokens);
+        let class = cursor.parse_top_level_class()?;
+        Ok(Some(class))
+    }
+
+    pub fn parse_files(&self, paths: &[String]) -> Result<Vec<ApexClass>> {
+        let mut classes = Vec::new();
+
+        for path in paths {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read file: {}", path))?;
+
+            match self.parse_file(&content) {
+                Ok(Some(class)) => classes.push(class),
+                Ok(None) => {}
+                Err(e) => warn!("Skipping {} (failed to parse): {:#}", path, e),
+            }
+        }
+
+        Ok(classes)
+    }
+}
+
+impl Default for ApexParser {
+    fn default() -> Self {
+        Self::new().unwrap()
+    }
+}
+
+/// `classes`（およびそのネストした `inner_classes`）に enum やインナークラスが含まれていれば警告する。
+///
+/// ネストした型はパーサーのモデルには取り込まれるが、`generator::TypeScriptGenerator` の出力には
+/// 含まれず、修飾名での参照も生成されない。この関数はそれらが出力から落ちることを
+/// ユーザーに知らせるだけで、出力そのものは変えない。
+pub fn warn_about_unemitted_nested_types(classes: &[ApexClass]) {
+    for class in classes {
+        if !class.inner_classes.is_empty() || !class.enums.is_empty() {
+            warn!(
+                "'{}' has nested class(es)/enum(s) captured by the parser, but they are NOT emitted \
+                 to TypeScript (no interfaces or enums are generated for them, and fields that use \
+                 them are not given qualified-name references)",
+                class.name
+            );
+        }
+        warn_about_unemitted_nested_types(&class.inner_classes);
+    }
+}
+
+/// `@AuraEnabled` が付いていれば `body.fields` に、なければ `body.missing_annotations` に積む。
+/// フィールド宣言（`;` 終端・初期化子付き・自動実装プロパティのいずれも）で共通して使う。
+fn record_field(
+    body: &mut ClassBody,
+    class_name: &str,
+    name: String,
+    span: Span,
+    field_type: String,
+    has_aura_enabled: bool,
+) {
+    if has_aura_enabled {
+        debug!("  Field: {} ({})", name, field_type);
+        body.fields.push(ApexField {
+            name,
+            field_type,
+            is_optional: false,
+            span,
+        });
+    } else {
+        warn!(
+            "  Skipping field '{}' in class '{}' (missing @AuraEnabled)",
+            name, class_name
+        );
+        body.missing_annotations.push(MissingAnnotation {
+            member_name: name,
+            span,
+        });
+    }
+}
+
+fn has_tsexport_annotation(tokens: &[Token]) -> bool {
+    tokens.iter().any(|t| match &t.kind {
+        TokenKind::BlockComment(content) => {
+            content.starts_with('*') && content.contains("@tsexport")
+        }
+        _ => false,
+    })
+}
+
+/// トークン列を先読みしながら状態を積み上げていく、再帰下降パーサー本体。
+///
+/// 文字列/コメント内のテキストはトークン化の時点で別種のトークンとして分離されているため
+/// （`public` というバイト列がたまたま含まれていても）宣言として誤検出することはない。
+/// `<...>` のネストと `{...}` のブレース深さは明示的にカウントするので、
+/// ジェネリクス引数のカンマとメソッド本体の中身は正しく読み飛ばされる。
+struct Cursor<'a> {
+    src: &'a str,
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(src: &'a str, tokens: &'a [Token]) -> Self {
+        Self {
+            src,
+            tokens,
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn skip_trivia(&mut self) {
+        while self.peek().is_trivia() {
+            self.advance();
+        }
+    }
+
+    /// ファイル全体から最初の `public ... class Name` または `public ... enum Name` 宣言を探して解析する。
+    /// トップレベルが enum の場合は、その enum だけを持つ `ApexClass` として返す
+    /// （`ApexClass` が唯一のファイル単位の戻り値であるため）。
+    fn parse_top_level_class(&mut self) -> Result<ApexClass> {
+        loop {
+            self.skip_trivia();
+            if matches!(self.peek().kind, TokenKind::Eof) {
+                bail!("Failed to find a top-level class or enum declaration");
+            }
+
+            let is_public = matches!(&self.peek().kind, TokenKind::Ident(s) if s == "public");
+            if !is_public {
+                self.advance();
+                continue;
+            }
+
+            let checkpoint = self.pos;
+            self.advance(); // "public"
+            loop {
+                self.skip_trivia();
+                match &self.peek().kind {
+                    TokenKind::Ident(s) if CLASS_MODIFIERS.contains(&s.as_str()) => {
+                        self.advance();
+                    }
+                    _ => break,
+                }
+            }
+
+            self.skip_trivia();
+            let is_enum = matches!(&self.peek().kind, TokenKind::Ident(s) if s == "enum");
+            let is_class = matches!(&self.peek().kind, TokenKind::Ident(s) if s == "class");
+            if !is_enum && !is_class {
+                // クラス/enum 宣言ではなかった。次のトークンから再探索する
+                self.pos = checkpoint + 1;
+                continue;
+            }
+            self.advance(); // "class" / "enum"
+
+            if is_enum {
+                let apex_enum = self.parse_enum_body()?;
+                debug!("Parsing top-level enum: {}", apex_enum.name);
+                return Ok(ApexClass {
+                    name: apex_enum.name.clone(),
+                    fields: Vec::new(),
+                    methods: Vec::new(),
+                    inner_classes: Vec::new(),
+                    enums: vec![apex_enum],
+                    missing_annotations: Vec::new(),
                 });
-            } else {
-                warn!(
-                    "  Skipping method '{}' in class '{}' (missing @AuraEnabled)",
-                    method_name, class_name
-                );
             }
+
+            self.skip_trivia();
+            let name_token = self.advance();
+            let name = match &name_token.kind {
+                TokenKind::Ident(s) => s.clone(),
+                other => bail!("Expected class name, found {:?}", other),
+            };
+
+            debug!("Parsing class: {}", name);
+
+            self.skip_to_brace_open()?;
+            self.advance(); // "{"
+            let body = self.parse_class_body(&name)?;
+
+            return Ok(ApexClass {
+                name,
+                fields: body.fields,
+                methods: body.methods,
+                inner_classes: body.inner_classes,
+                enums: body.enums,
+                missing_annotations: body.missing_annotations,
+            });
         }
+    }
 
-        Ok(Some(ApexClass {
-            name: class_name,
-            fields,
-            methods,
-        }))
+    /// `{` が見つかるまで（`extends`/`implements` 節などを）読み飛ばす。`{` 自体は消費しない。
+    fn skip_to_brace_open(&mut self) -> Result<()> {
+        loop {
+            match &self.peek().kind {
+                TokenKind::Punct('{') => return Ok(()),
+                TokenKind::Eof => bail!("Unexpected end of file before class body"),
+                _ => {
+                    self.advance();
+                }
+            }
+        }
     }
 
-    fn parse_parameters(&self, params_str: &str) -> Vec<ApexParameter> {
-        if params_str.trim().is_empty() {
-            return Vec::new();
+    /// `open` が現在位置にある状態で呼び出し、対応する `close` まで読み飛ばす（両方消費する）。
+    fn skip_balanced(&mut self, open: char, close: char) -> Result<()> {
+        let mut depth = 0usize;
+        loop {
+            match &self.peek().kind {
+                TokenKind::Punct(c) if *c == open => {
+                    depth += 1;
+                    self.advance();
+                }
+                TokenKind::Punct(c) if *c == close => {
+                    depth -= 1;
+                    self.advance();
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                TokenKind::Eof => bail!("Unbalanced '{}' / '{}'", open, close),
+                _ => {
+                    self.advance();
+                }
+            }
         }
+    }
+
+    /// クラス本体（最初の `{` を消費済みの状態）を走査し、`public` なフィールド/メソッド/
+    /// ネストしたクラス・enum を集める。
+    fn parse_class_body(&mut self, class_name: &str) -> Result<ClassBody> {
+        let mut body = ClassBody::default();
+
+        loop {
+            let has_aura_enabled = self.skip_member_trivia_and_annotations()?;
 
-        params_str
-            .split(',')
-            .filter_map(|param| {
-                let parts: Vec<&str> = param.trim().split_whitespace().collect();
-                if parts.len() >= 2 {
-                    Some(ApexParameter {
-                        param_type: parts[0].to_string(),
-                        name: parts[1].to_string(),
-                    })
+            match &self.peek().kind {
+                TokenKind::Punct('}') => {
+                    self.advance();
+                    return Ok(body);
+                }
+                TokenKind::Eof => bail!("Unexpected end of file in class '{}' body", class_name),
+                TokenKind::Ident(s) if s == "public" => {
+                    self.advance();
+                    self.parse_member_after_public(class_name, has_aura_enabled, &mut body)?;
+                }
+                _ => {
+                    // private/protected/global なメンバーや静的初期化子は @tsexport の対象外なので読み飛ばす
+                    self.skip_unknown_member()?;
+                }
+            }
+        }
+    }
+
+    /// コメントと `@Annotation(...)` の並びを読み飛ばし、途中に `@AuraEnabled` があったかを返す。
+    fn skip_member_trivia_and_annotations(&mut self) -> Result<bool> {
+        let mut has_aura_enabled = false;
+        loop {
+            match &self.peek().kind {
+                TokenKind::LineComment(_) | TokenKind::BlockComment(_) => {
+                    self.advance();
+                }
+                TokenKind::Punct('@') => {
+                    self.advance();
+                    let name_token = self.advance();
+                    if let TokenKind::Ident(name) = &name_token.kind {
+                        if name == "AuraEnabled" {
+                            has_aura_enabled = true;
+                        }
+                    }
+                    if matches!(&self.peek().kind, TokenKind::Punct('(')) {
+                        self.skip_balanced('(', ')')?;
+                    }
+                }
+                _ => return Ok(has_aura_enabled),
+            }
+        }
+    }
+
+    fn parse_member_after_public(
+        &mut self,
+        class_name: &str,
+        has_aura_enabled: bool,
+        body: &mut ClassBody,
+    ) -> Result<()> {
+        let mut is_static = false;
+        loop {
+            self.skip_trivia();
+            match &self.peek().kind {
+                TokenKind::Ident(s) if s == "static" => {
+                    is_static = true;
+                    self.advance();
+                }
+                TokenKind::Ident(s) if MEMBER_MODIFIERS.contains(&s.as_str()) => {
+                    self.advance();
+                }
+                _ => break,
+            }
+        }
+
+        self.skip_trivia();
+        if let TokenKind::Ident(s) = &self.peek().kind {
+            match s.as_str() {
+                "enum" => {
+                    self.advance();
+                    let apex_enum = self.parse_enum_body()?;
+                    debug!("  Nested enum: {}", apex_enum.name);
+                    body.enums.push(apex_enum);
+                    return Ok(());
+                }
+                "class" => {
+                    self.advance();
+                    let inner = self.parse_nested_class()?;
+                    debug!("  Nested class: {}", inner.name);
+                    body.inner_classes.push(inner);
+                    return Ok(());
+                }
+                "interface" => {
+                    // インターフェースは現状では非対応。中身は触らず丸ごと読み飛ばす
+                    self.advance();
+                    self.skip_trivia();
+                    self.advance(); // 型名
+                    self.skip_to_brace_open()?;
+                    self.skip_balanced('{', '}')?;
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+
+        let member_type = self.parse_type()?;
+        self.skip_trivia();
+
+        if matches!(&self.peek().kind, TokenKind::Punct('(')) {
+            // 戻り値型を持たないコンストラクタ。元の実装と同様、フィールド/メソッドとしては扱わない
+            self.advance();
+            self.parse_parameters()?;
+            self.skip_member_tail()?;
+            return Ok(());
+        }
+
+        let name_token = self.advance();
+        let span = Span::from(&name_token);
+        let name = match &name_token.kind {
+            TokenKind::Ident(n) => n.clone(),
+            other => bail!("Expected member name, found {:?}", other),
+        };
+
+        self.skip_trivia();
+        match &self.peek().kind {
+            TokenKind::Punct(';') => {
+                self.advance();
+                record_field(body, class_name, name, span, member_type, has_aura_enabled);
+            }
+            TokenKind::Punct('=') => {
+                // `public static final String STATUS = 'Active';` のような初期化子付きフィールド
+                self.skip_initializer()?;
+                record_field(body, class_name, name, span, member_type, has_aura_enabled);
+            }
+            TokenKind::Punct('{') => {
+                // `public Integer Count { get; set; }` のような自動実装プロパティ
+                self.skip_balanced('{', '}')?;
+                self.skip_trivia();
+                match &self.peek().kind {
+                    TokenKind::Punct('=') => self.skip_initializer()?,
+                    TokenKind::Punct(';') => {
+                        self.advance();
+                    }
+                    _ => {}
+                }
+                record_field(body, class_name, name, span, member_type, has_aura_enabled);
+            }
+            TokenKind::Punct('(') => {
+                self.advance();
+                let parameters = self.parse_parameters()?;
+                self.skip_member_tail()?;
+                if has_aura_enabled {
+                    debug!("  Method: {} ({:?}) -> {}", name, parameters, member_type);
+                    body.methods.push(ApexMethod {
+                        name,
+                        return_type: member_type,
+                        parameters,
+                        is_static,
+                        span,
+                    });
                 } else {
-                    None
+                    warn!(
+                        "  Skipping method '{}' in class '{}' (missing @AuraEnabled)",
+                        name, class_name
+                    );
+                    body.missing_annotations.push(MissingAnnotation {
+                        member_name: name,
+                        span,
+                    });
                 }
-            })
-            .collect()
+            }
+            other => bail!(
+                "Expected ';', '=', '{{' or '(' after member name, found {:?}",
+                other
+            ),
+        }
+
+        Ok(())
     }
 
-    pub fn parse_files(&self, paths: &[String]) -> Result<Vec<ApexClass>> {
-        let mut classes = Vec::new();
+    /// `=` を消費済みでない状態で呼び出し、初期化子を次のトップレベル `;` まで読み飛ばす
+    /// （`(`/`[`/`{` のネストはトップレベルの `;` と誤認しないよう深さで追跡する）。
+    fn skip_initializer(&mut self) -> Result<()> {
+        self.advance(); // "="
+        let mut depth = 0usize;
+        loop {
+            match &self.peek().kind {
+                TokenKind::Punct(c) if matches!(c, '(' | '[' | '{') => {
+                    depth += 1;
+                    self.advance();
+                }
+                TokenKind::Punct(c) if matches!(c, ')' | ']' | '}') => {
+                    depth = depth.saturating_sub(1);
+                    self.advance();
+                }
+                TokenKind::Punct(';') if depth == 0 => {
+                    self.advance();
+                    return Ok(());
+                }
+                TokenKind::Eof => bail!("Unexpected end of file in member initializer"),
+                _ => {
+                    self.advance();
+                }
+            }
+        }
+    }
 
-        for path in paths {
-            let content = fs::read_to_string(path)
-                .with_context(|| format!("Failed to read file: {}", path))?;
+    /// ネストした `public class Name { ... }` を解析する。export の可否はアノテーションではなく
+    /// 外側のクラスが `@tsexport` されているかどうかに従う。
+    fn parse_nested_class(&mut self) -> Result<ApexClass> {
+        self.skip_trivia();
+        let name_token = self.advance();
+        let name = match &name_token.kind {
+            TokenKind::Ident(s) => s.clone(),
+            other => bail!("Expected nested class name, found {:?}", other),
+        };
+
+        self.skip_to_brace_open()?;
+        self.advance(); // "{"
+        let body = self.parse_class_body(&name)?;
+
+        Ok(ApexClass {
+            name,
+            fields: body.fields,
+            methods: body.methods,
+            inner_classes: body.inner_classes,
+            enums: body.enums,
+            missing_annotations: body.missing_annotations,
+        })
+    }
 
-            if let Some(class) = self.parse_file(&content)? {
-                classes.push(class);
+    /// `enum Name { A, B, C }` の名前と値一覧を解析する。`enum` キーワード自体は消費済みの状態で呼ぶ。
+    fn parse_enum_body(&mut self) -> Result<ApexEnum> {
+        self.skip_trivia();
+        let name_token = self.advance();
+        let name = match &name_token.kind {
+            TokenKind::Ident(s) => s.clone(),
+            other => bail!("Expected enum name, found {:?}", other),
+        };
+
+        self.skip_to_brace_open()?;
+        self.advance(); // "{"
+
+        let mut values = Vec::new();
+        loop {
+            self.skip_trivia();
+            match &self.peek().kind {
+                TokenKind::Punct('}') => {
+                    self.advance();
+                    return Ok(ApexEnum { name, values });
+                }
+                TokenKind::Punct(',') => {
+                    self.advance();
+                }
+                TokenKind::Ident(v) => {
+                    values.push(v.clone());
+                    self.advance();
+                }
+                TokenKind::Eof => bail!("Unexpected end of file in enum '{}' body", name),
+                other => bail!("Unexpected token in enum '{}' body: {:?}", name, other),
             }
         }
+    }
 
-        Ok(classes)
+    /// メソッドシグネチャの後に続く `{ ... }`（本体）か `;`（抽象メソッド）を読み飛ばす。
+    fn skip_member_tail(&mut self) -> Result<()> {
+        self.skip_trivia();
+        match &self.peek().kind {
+            TokenKind::Punct('{') => self.skip_balanced('{', '}'),
+            TokenKind::Punct(';') => {
+                self.advance();
+                Ok(())
+            }
+            TokenKind::Eof => bail!("Unexpected end of file after member signature"),
+            other => bail!("Expected method body or ';', found {:?}", other),
+        }
+    }
+
+    /// `public` ではないメンバー宣言を、次のトップレベル `;` か対応する `{...}` まで読み飛ばす。
+    fn skip_unknown_member(&mut self) -> Result<()> {
+        loop {
+            match &self.peek().kind {
+                TokenKind::Punct('{') => return self.skip_balanced('{', '}'),
+                TokenKind::Punct(';') => {
+                    self.advance();
+                    return Ok(());
+                }
+                TokenKind::Punct('}') => return Ok(()),
+                TokenKind::Eof => bail!("Unexpected end of file while skipping class member"),
+                _ => {
+                    self.advance();
+                }
+            }
+        }
+    }
+
+    /// 型名を解析する。`Map<String, List<Account>>` のようなネストしたジェネリクスや
+    /// `String[]` の配列サフィックスも、ソース上の範囲をそのまま切り出すことで型文字列にする。
+    fn parse_type(&mut self) -> Result<String> {
+        self.skip_trivia();
+        let start_token = self.peek().clone();
+        match &start_token.kind {
+            TokenKind::Ident(_) => {
+                self.advance();
+            }
+            other => bail!("Expected type, found {:?}", other),
+        }
+
+        let mut end = start_token.end;
+        if matches!(&self.peek().kind, TokenKind::Punct('<')) {
+            end = self.skip_generic_args()?;
+        }
+        while matches!(&self.peek().kind, TokenKind::Punct('[')) {
+            self.advance();
+            if matches!(&self.peek().kind, TokenKind::Punct(']')) {
+                end = self.advance().end;
+            }
+        }
+
+        Ok(self.src[start_token.start..end].trim().to_string())
+    }
+
+    /// 現在位置の `<` から、対応する `>` までの深さを追跡して読み飛ばす。戻り値は末尾 `>` の終端位置。
+    fn skip_generic_args(&mut self) -> Result<usize> {
+        let mut depth = 0usize;
+        loop {
+            match &self.peek().kind {
+                TokenKind::Punct('<') => {
+                    depth += 1;
+                    self.advance();
+                }
+                TokenKind::Punct('>') => {
+                    let end = self.peek().end;
+                    depth -= 1;
+                    self.advance();
+                    if depth == 0 {
+                        return Ok(end);
+                    }
+                }
+                TokenKind::Eof => bail!("Unbalanced generic type arguments"),
+                _ => {
+                    self.advance();
+                }
+            }
+        }
+    }
+
+    /// `(` を消費済みの状態で呼び出し、対応する `)` までをトップレベルのカンマで区切って解析する。
+    /// ジェネリクス引数の中のカンマは `parse_type` がまとめて読み飛ばすため分割対象にならない。
+    fn parse_parameters(&mut self) -> Result<Vec<ApexParameter>> {
+        let mut parameters = Vec::new();
+
+        self.skip_trivia();
+        if matches!(&self.peek().kind, TokenKind::Punct(')')) {
+            self.advance();
+            return Ok(parameters);
+        }
+
+        loop {
+            loop {
+                self.skip_trivia();
+                match &self.peek().kind {
+                    TokenKind::Punct('@') => {
+                        self.advance();
+                        self.advance(); // アノテーション名
+                        if matches!(&self.peek().kind, TokenKind::Punct('(')) {
+                            self.skip_balanced('(', ')')?;
+                        }
+                    }
+                    TokenKind::Ident(s) if MEMBER_MODIFIERS.contains(&s.as_str()) => {
+                        self.advance();
+                    }
+                    _ => break,
+                }
+            }
+
+            let param_type = self.parse_type()?;
+            self.skip_trivia();
+            let name_token = self.advance();
+            let name = match &name_token.kind {
+                TokenKind::Ident(n) => n.clone(),
+                other => bail!("Expected parameter name, found {:?}", other),
+            };
+            parameters.push(ApexParameter { param_type, name });
+
+            self.skip_trivia();
+            match &self.peek().kind {
+                TokenKind::Punct(',') => {
+                    self.advance();
+                }
+                TokenKind::Punct(')') => {
+                    self.advance();
+                    break;
+                }
+                TokenKind::Eof => bail!("Unbalanced parameter list"),
+                other => bail!("Unexpected token in parameter list: {:?}", other),
+            }
+        }
+
+        Ok(parameters)
     }
 }
 
-impl Default for ApexParser {
-    fn default() -> Self {
-        Self::new().unwrap()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_constant_with_initializer() {
+        let src = r#"
+            /** @tsexport */
+            public class Account {
+                @AuraEnabled
+                public static final String STATUS = 'Active';
+            }
+        "#;
+
+        let class = ApexParser::new().unwrap().parse_file(src).unwrap().unwrap();
+
+        assert_eq!(class.fields.len(), 1);
+        assert_eq!(class.fields[0].name, "STATUS");
+        assert_eq!(class.fields[0].field_type, "String");
+    }
+
+    #[test]
+    fn parses_auto_implemented_property() {
+        let src = r#"
+            /** @tsexport */
+            public class Account {
+                @AuraEnabled
+                public Integer Count { get; set; }
+            }
+        "#;
+
+        let class = ApexParser::new().unwrap().parse_file(src).unwrap().unwrap();
+
+        assert_eq!(class.fields.len(), 1);
+        assert_eq!(class.fields[0].name, "Count");
+        assert_eq!(class.fields[0].field_type, "Integer");
+    }
+
+    #[test]
+    fn parses_nested_generic_parameter_type() {
+        let src = r#"
+            /** @tsexport */
+            public class AccountService {
+                @AuraEnabled
+                public static void save(Map<String, List<Account>> acctsById) {
+                }
+            }
+        "#;
+
+        let class = ApexParser::new().unwrap().parse_file(src).unwrap().unwrap();
+
+        assert_eq!(class.methods.len(), 1);
+        assert_eq!(class.methods[0].parameters.len(), 1);
+        assert_eq!(class.methods[0].parameters[0].name, "acctsById");
+        assert_eq!(
+            class.methods[0].parameters[0].param_type,
+            "Map<String, List<Account>>"
+        );
+    }
+
+    #[test]
+    fn method_body_keywords_and_semicolons_are_not_mistaken_for_declarations() {
+        let src = r#"
+            /** @tsexport */
+            public class Account {
+                @AuraEnabled
+                public static String describe() {
+                    // public String fake; looks like a declaration but isn't
+                    String note = 'this mentions public and ends with a semicolon;';
+                    if (true) {
+                        public_helper();
+                    }
+                    return note;
+                }
+
+                @AuraEnabled
+                public String Name { get; set; }
+            }
+        "#;
+
+        let class = ApexParser::new().unwrap().parse_file(src).unwrap().unwrap();
+
+        assert_eq!(class.methods.len(), 1);
+        assert_eq!(class.methods[0].name, "describe");
+        assert_eq!(class.fields.len(), 1);
+        assert_eq!(class.fields[0].name, "Name");
+        assert!(class.missing_annotations.is_empty());
+    }
+
+    #[test]
+    fn annotation_like_text_in_strings_and_comments_is_ignored() {
+        let src = r#"
+            /** @tsexport */
+            public class Account {
+                /* an old version of this field was: @AuraEnabled public String Legacy; */
+                @AuraEnabled
+                public String Description { get; set; }
+
+                public void helper() {
+                    String doc = '@AuraEnabled public void fake() {}';
+                }
+            }
+        "#;
+
+        let class = ApexParser::new().unwrap().parse_file(src).unwrap().unwrap();
+
+        assert_eq!(class.fields.len(), 1);
+        assert_eq!(class.fields[0].name, "Description");
+        assert_eq!(class.methods.len(), 0);
+        assert_eq!(class.missing_annotations.len(), 1);
+        assert_eq!(class.missing_annotations[0].member_name, "helper");
+    }
+
+    #[test]
+    fn parses_nested_enum_values() {
+        let src = r#"
+            /** @tsexport */
+            public class Account {
+                public enum Status {
+                    ACTIVE, INACTIVE, PENDING
+                }
+            }
+        "#;
+
+        let class = ApexParser::new().unwrap().parse_file(src).unwrap().unwrap();
+
+        assert_eq!(class.enums.len(), 1);
+        assert_eq!(class.enums[0].name, "Status");
+        assert_eq!(
+            class.enums[0].values,
+            vec![
+                "ACTIVE".to_string(),
+                "INACTIVE".to_string(),
+                "PENDING".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn recursively_parses_inner_class_fields_and_missing_annotations() {
+        let src = r#"
+            /** @tsexport */
+            public class Account {
+                public class AccountDto {
+                    @AuraEnabled
+                    public String name;
+                    public String internalNote;
+                }
+            }
+        "#;
+
+        let class = ApexParser::new().unwrap().parse_file(src).unwrap().unwrap();
+
+        assert_eq!(class.inner_classes.len(), 1);
+        let dto = &class.inner_classes[0];
+        assert_eq!(dto.name, "AccountDto");
+        assert_eq!(dto.fields.len(), 1);
+        assert_eq!(dto.fields[0].name, "name");
+        assert_eq!(dto.missing_annotations.len(), 1);
+        assert_eq!(dto.missing_annotations[0].member_name, "internalNote");
     }
 }
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use tracing::warn;
+use walkdir::WalkDir;
+
+use crate::generator::TypeScriptGenerator;
+use crate::parser::{warn_about_unemitted_nested_types, ApexClass, ApexParser};
+
+/// 短時間に連続するファイルシステムイベントをまとめる猶予時間
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// `--watch` モードの本体。初回フルスキャンの後、変更されたパスだけを再解析しながら
+/// `output` を再生成し続ける。パースエラーが起きても監視は継続する。
+pub fn run(input: &Path, output: &Path, verbose: bool) -> Result<()> {
+    let parser = ApexParser::new()?;
+    let mut classes_by_path: HashMap<PathBuf, ApexClass> = HashMap::new();
+
+    for path in collect_cls_files(input) {
+        match parse_one(&parser, &path) {
+            Ok(Some(class)) => {
+                classes_by_path.insert(path, class);
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to parse {}: {:#}", path.display(), e),
+        }
+    }
+
+    rebuild(&classes_by_path, output, verbose)?;
+
+    let (tx, rx) = channel();
+    let mut watcher =
+        RecommendedWatcher::new(tx, Config::default()).context("Failed to create file watcher")?;
+    watcher
+        .watch(input, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch directory: {}", input.display()))?;
+
+    println!(
+        "Watching {} for changes... (Ctrl+C to stop)",
+        input.display()
+    );
+
+    loop {
+        let Ok(first_event) = rx.recv() else {
+            return Ok(());
+        };
+
+        let mut changed_paths = Vec::new();
+        collect_event_paths(first_event, &mut changed_paths);
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(event) => collect_event_paths(event, &mut changed_paths),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+
+        changed_paths.sort();
+        changed_paths.dedup();
+        if changed_paths.is_empty() {
+            continue;
+        }
+
+        let mut updated = 0usize;
+        let mut removed = 0usize;
+        for path in &changed_paths {
+            if path.exists() {
+                match parse_one(&parser, path) {
+                    Ok(Some(class)) => {
+                        classes_by_path.insert(path.clone(), class);
+                        updated += 1;
+                    }
+                    Ok(None) => {
+                        if classes_by_path.remove(path).is_some() {
+                            removed += 1;
+                        }
+                    }
+                    Err(e) => warn!("Failed to parse {}: {:#}", path.display(), e),
+                }
+            } else if classes_by_path.remove(path).is_some() {
+                removed += 1;
+            }
+        }
+
+        println!(
+            "Rebuild: {} file(s) changed ({} updated, {} removed)",
+            changed_paths.len(),
+            updated,
+            removed
+        );
+
+        if let Err(e) = rebuild(&classes_by_path, output, verbose) {
+            warn!("Failed to regenerate {}: {:#}", output.display(), e);
+        }
+    }
+}
+
+fn collect_cls_files(input: &Path) -> Vec<PathBuf> {
+    WalkDir::new(input)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "cls"))
+        .map(|e| e.path().to_path_buf())
+        .collect()
+}
+
+fn parse_one(parser: &ApexParser, path: &Path) -> Result<Option<ApexClass>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+    parser.parse_file(&content)
+}
+
+fn collect_event_paths(event: notify::Result<Event>, out: &mut Vec<PathBuf>) {
+    match event {
+        Ok(event) => out.extend(
+            event
+                .paths
+                .into_iter()
+                .filter(|p| p.extension().is_some_and(|ext| ext == "cls")),
+        ),
+        Err(e) => warn!("Watch error: {}", e),
+    }
+}
+
+fn rebuild(
+    classes_by_path: &HashMap<PathBuf, ApexClass>,
+    output: &Path,
+    verbose: bool,
+) -> Result<()> {
+    let mut classes: Vec<ApexClass> = classes_by_path.values().cloned().collect();
+    classes.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if classes.is_empty() {
+        println!("No classes with @tsexport annotation found");
+        return Ok(());
+    }
+
+    warn_about_unemitted_nested_types(&classes);
+
+    let generator = TypeScriptGenerator::new();
+    let typescript_code = generator.generate(&classes);
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create output directory: {}", parent.display()))?;
+    }
+    fs::write(output, typescript_code)
+        .with_context(|| format!("Failed to write output file: {}", output.display()))?;
+
+    if verbose {
+        println!(
+            "✓ Regenerated {} ({} interface(s))",
+            output.display(),
+            classes.len()
+        );
+    }
+
+    Ok(())
+}